@@ -1,10 +1,17 @@
-use rustler::{Atom, Env, Error, NifResult, ResourceArc, Term};
+use rustler::{Atom, Binary, Encoder, Env, Error, LocalPid, NifResult, OwnedEnv, ResourceArc, Term};
 use std::collections::HashMap;
+use std::fs::File;
+use std::os::unix::io::AsFd;
 use std::sync::{Arc, Mutex};
 use wayland_client::{
-    protocol::{wl_compositor, wl_registry, wl_surface},
-    Connection, Dispatch, EventQueue, QueueHandle,
+    protocol::{
+        wl_buffer, wl_compositor, wl_keyboard, wl_output, wl_pointer, wl_region, wl_registry,
+        wl_seat, wl_shm, wl_shm_pool, wl_surface,
+    },
+    Connection, Dispatch, EventQueue, Proxy, QueueHandle, WEnum,
 };
+use wayland_protocols::xdg::shell::client::{xdg_surface, xdg_toplevel, xdg_wm_base};
+use wayland_protocols_wlr::layer_shell::v1::client::zwlr_layer_shell_v1;
 
 // Global storage for wayland objects (since they may not be Send + Sync)
 lazy_static::lazy_static! {
@@ -12,11 +19,78 @@ lazy_static::lazy_static! {
         Arc::new(Mutex::new(HashMap::new()));
     static ref SURFACES: Arc<Mutex<HashMap<u32, wl_surface::WlSurface>>> = 
         Arc::new(Mutex::new(HashMap::new()));
-    static ref REGISTRIES: Arc<Mutex<HashMap<u32, wl_registry::WlRegistry>>> = 
+    static ref REGISTRIES: Arc<Mutex<HashMap<u32, wl_registry::WlRegistry>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    static ref OUTPUTS: Arc<Mutex<HashMap<u32, OutputInfo>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    // Globals currently advertised by the compositor (entries are dropped on
+    // `GlobalRemove` and on `disconnect/1`), keyed by registry name.
+    // Shared across every `AppData` so it survives being rebuilt on each
+    // dispatch cycle, and lets `bind_global` validate requested versions.
+    static ref GLOBALS: Arc<Mutex<HashMap<u32, GlobalInfo>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    static ref COMPOSITORS: Arc<Mutex<HashMap<u32, wl_compositor::WlCompositor>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    static ref SEATS: Arc<Mutex<HashMap<u32, wl_seat::WlSeat>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    static ref BOUND_OUTPUTS: Arc<Mutex<HashMap<u32, wl_output::WlOutput>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    static ref BOUND_SHMS: Arc<Mutex<HashMap<u32, wl_shm::WlShm>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    static ref BOUND_WM_BASES: Arc<Mutex<HashMap<u32, xdg_wm_base::XdgWmBase>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    static ref LAYER_SHELLS: Arc<Mutex<HashMap<u32, zwlr_layer_shell_v1::ZwlrLayerShellV1>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    static ref REGIONS: Arc<Mutex<HashMap<u32, wl_region::WlRegion>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    // Pointer/keyboard are (re)created per the seat's `Capabilities` event, so
+    // these are keyed by the same tag as the owning `wl_seat`.
+    static ref POINTERS: Arc<Mutex<HashMap<u32, wl_pointer::WlPointer>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    static ref KEYBOARDS: Arc<Mutex<HashMap<u32, wl_keyboard::WlKeyboard>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    // Registered Elixir event handlers, keyed by the connection or object id
+    // that Dispatch user-data tags events with.
+    static ref HANDLERS: Arc<Mutex<HashMap<u32, LocalPid>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    // The bound wl_shm global (there is only ever one per connection) and the
+    // pixel formats it advertised via `Format` events.
+    static ref SHM: Arc<Mutex<Option<wl_shm::WlShm>>> = Arc::new(Mutex::new(None));
+    static ref SHM_FORMATS: Arc<Mutex<Vec<u32>>> = Arc::new(Mutex::new(Vec::new()));
+    static ref SHM_POOLS: Arc<Mutex<HashMap<u32, ShmPoolState>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    static ref BUFFERS: Arc<Mutex<HashMap<u32, wl_buffer::WlBuffer>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    // The queue handle of the most recently established connection, needed by
+    // NIFs (like `create_buffer/6`) that don't take a `DisplayResource`.
+    static ref GLOBAL_QH: Arc<Mutex<Option<QueueHandle<AppData>>>> = Arc::new(Mutex::new(None));
+    static ref XDG_WM_BASE: Arc<Mutex<Option<xdg_wm_base::XdgWmBase>>> = Arc::new(Mutex::new(None));
+    static ref XDG_SURFACES: Arc<Mutex<HashMap<u32, xdg_surface::XdgSurface>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+    static ref XDG_TOPLEVELS: Arc<Mutex<HashMap<u32, xdg_toplevel::XdgToplevel>>> =
         Arc::new(Mutex::new(HashMap::new()));
     static ref NEXT_ID: Arc<Mutex<u32>> = Arc::new(Mutex::new(1));
 }
 
+struct ShmPoolState {
+    pool: wl_shm_pool::WlShmPool,
+    mmap: Arc<Mutex<memmap2::MmapMut>>,
+    size: usize,
+    // Keeps the backing memfd open for as long as the compositor may still
+    // reference the pool.
+    _file: File,
+}
+
+// Encode and send `msg` to whichever PID (if any) is registered for `tag`.
+fn notify_handler<T: Encoder>(tag: u32, msg: T) {
+    let pid = match HANDLERS.lock().unwrap().get(&tag) {
+        Some(pid) => *pid,
+        None => return,
+    };
+    let mut owned_env = OwnedEnv::new();
+    let _ = owned_env.send_and_clear(&pid, |env| msg.encode(env));
+}
+
 fn get_next_id() -> u32 {
     let mut id = NEXT_ID.lock().unwrap();
     let current = *id;
@@ -31,6 +105,27 @@ mod atoms {
         nil,
         not_found,
         nif_not_loaded,
+        wl_registry,
+        wl_surface,
+        wl_buffer,
+        xdg_surface,
+        xdg_toplevel,
+        global,
+        global_remove,
+        enter,
+        leave,
+        release,
+        configure,
+        close,
+        unsupported_version,
+        pointer,
+        keyboard,
+        motion,
+        button,
+        axis,
+        key,
+        modifiers,
+        keymap,
     }
 }
 
@@ -74,10 +169,66 @@ struct SurfaceResource {
 unsafe impl Send for SurfaceResource {}
 unsafe impl Sync for SurfaceResource {}
 
+#[derive(Debug)]
+struct ShmPoolResource {
+    pool_id: u32,
+}
+
+// Safety: Only contains Send + Sync types
+unsafe impl Send for ShmPoolResource {}
+unsafe impl Sync for ShmPoolResource {}
+
+#[derive(Debug)]
+struct BufferResource {
+    buffer_id: u32,
+}
+
+// Safety: Only contains Send + Sync types
+unsafe impl Send for BufferResource {}
+unsafe impl Sync for BufferResource {}
+
+#[derive(Debug)]
+struct XdgSurfaceResource {
+    xdg_surface_id: u32,
+}
+
+// Safety: Only contains Send + Sync types
+unsafe impl Send for XdgSurfaceResource {}
+unsafe impl Sync for XdgSurfaceResource {}
+
+#[derive(Debug)]
+struct XdgToplevelResource {
+    xdg_toplevel_id: u32,
+}
+
+// Safety: Only contains Send + Sync types
+unsafe impl Send for XdgToplevelResource {}
+unsafe impl Sync for XdgToplevelResource {}
+
+#[derive(Debug)]
+struct RegionResource {
+    region_id: u32,
+}
+
+// Safety: Only contains Send + Sync types
+unsafe impl Send for RegionResource {}
+unsafe impl Sync for RegionResource {}
+
+// A global bound via `bind_global/4`. `interface` says which per-interface
+// map (`COMPOSITORS`, `SEATS`, ...) `id` should be looked up in.
+#[derive(Debug)]
+struct BoundGlobalResource {
+    id: u32,
+    interface: String,
+}
+
+// Safety: Only contains Send + Sync types
+unsafe impl Send for BoundGlobalResource {}
+unsafe impl Sync for BoundGlobalResource {}
+
 #[derive(Debug)]
 struct RegistryResource {
     registry_id: u32,
-    globals: Arc<Mutex<HashMap<u32, GlobalInfo>>>,
 }
 
 // Safety: Only contains Send + Sync types
@@ -90,28 +241,327 @@ struct GlobalInfo {
     version: u32,
 }
 
+#[derive(Debug, Clone, Default)]
+struct OutputMode {
+    width: i32,
+    height: i32,
+    refresh: i32,
+    current: bool,
+    preferred: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+struct OutputInfo {
+    x: i32,
+    y: i32,
+    physical_width: i32,
+    physical_height: i32,
+    subpixel: i32,
+    make: String,
+    model: String,
+    transform: i32,
+    scale: i32,
+    name: String,
+    description: String,
+    modes: Vec<OutputMode>,
+    // Set once the compositor sends `Done` for this output, i.e. once its
+    // initial burst of Geometry/Mode/Scale events has actually arrived.
+    done: bool,
+}
+
+impl OutputInfo {
+    fn current_mode(&self) -> Option<&OutputMode> {
+        self.modes
+            .iter()
+            .find(|m| m.current)
+            .or_else(|| self.modes.last())
+    }
+}
+
+// Shape handed back to Elixir from `list_outputs/1`.
+#[derive(Debug, Clone, rustler::NifMap)]
+struct OutputSummary {
+    name: String,
+    make: String,
+    model: String,
+    width: i32,
+    height: i32,
+    refresh: i32,
+    scale: i32,
+    x: i32,
+    y: i32,
+}
+
+impl From<&OutputInfo> for OutputSummary {
+    fn from(info: &OutputInfo) -> Self {
+        let mode = info.current_mode();
+        OutputSummary {
+            name: info.name.clone(),
+            make: info.make.clone(),
+            model: info.model.clone(),
+            width: mode.map(|m| m.width).unwrap_or(0),
+            height: mode.map(|m| m.height).unwrap_or(0),
+            refresh: mode.map(|m| m.refresh).unwrap_or(0),
+            scale: info.scale,
+            x: info.x,
+            y: info.y,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct AppData {
     globals: Arc<Mutex<HashMap<u32, GlobalInfo>>>,
+    outputs: Arc<Mutex<HashMap<u32, OutputInfo>>>,
 }
 
-impl Dispatch<wl_registry::WlRegistry, ()> for AppData {
+impl Dispatch<wl_registry::WlRegistry, u32> for AppData {
     fn event(
         state: &mut Self,
         _registry: &wl_registry::WlRegistry,
         event: wl_registry::Event,
-        _data: &(),
+        data: &u32,
         _conn: &Connection,
         _qhandle: &QueueHandle<AppData>,
     ) {
         match event {
             wl_registry::Event::Global { name, interface, version } => {
+                // `get_registry/1` spins up additional registries on the same
+                // connection, and the compositor re-advertises every existing
+                // global to each new one. `GLOBALS` is shared across all of
+                // them, so a name already present here has already been
+                // bound once; skip it instead of leaking a duplicate proxy
+                // and clobbering the `SHM`/`XDG_WM_BASE` singletons.
+                let already_bound = state.globals.lock().unwrap().contains_key(&name);
+
+                if !already_bound {
+                    if interface == "wl_output" {
+                        _registry.bind::<wl_output::WlOutput, u32, AppData>(
+                            name,
+                            version.min(4),
+                            _qhandle,
+                            name,
+                        );
+                        state.outputs.lock().unwrap().entry(name).or_default();
+                    }
+
+                    if interface == "wl_shm" {
+                        let shm = _registry.bind::<wl_shm::WlShm, (), AppData>(
+                            name,
+                            version.min(1),
+                            _qhandle,
+                            (),
+                        );
+                        *SHM.lock().unwrap() = Some(shm);
+                    }
+
+                    if interface == "wl_seat" {
+                        let seat = _registry.bind::<wl_seat::WlSeat, u32, AppData>(
+                            name,
+                            version.min(7),
+                            _qhandle,
+                            name,
+                        );
+                        SEATS.lock().unwrap().insert(name, seat);
+                    }
+
+                    if interface == "xdg_wm_base" {
+                        let wm_base = _registry.bind::<xdg_wm_base::XdgWmBase, (), AppData>(
+                            name,
+                            version.min(5),
+                            _qhandle,
+                            (),
+                        );
+                        *XDG_WM_BASE.lock().unwrap() = Some(wm_base);
+                    }
+                }
+
+                notify_handler(*data, (atoms::wl_registry(), atoms::global(), name, interface.clone(), version));
+
                 let mut globals = state.globals.lock().unwrap();
                 globals.insert(name, GlobalInfo { interface, version });
             }
             wl_registry::Event::GlobalRemove { name } => {
+                notify_handler(*data, (atoms::wl_registry(), atoms::global_remove(), name));
+
                 let mut globals = state.globals.lock().unwrap();
                 globals.remove(&name);
+                state.outputs.lock().unwrap().remove(&name);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<wl_output::WlOutput, u32> for AppData {
+    fn event(
+        state: &mut Self,
+        _output: &wl_output::WlOutput,
+        event: wl_output::Event,
+        data: &u32,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<AppData>,
+    ) {
+        let mut outputs = state.outputs.lock().unwrap();
+        let info = outputs.entry(*data).or_default();
+
+        match event {
+            wl_output::Event::Geometry {
+                x,
+                y,
+                physical_width,
+                physical_height,
+                subpixel,
+                make,
+                model,
+                transform,
+            } => {
+                info.x = x;
+                info.y = y;
+                info.physical_width = physical_width;
+                info.physical_height = physical_height;
+                info.subpixel = if let WEnum::Value(v) = subpixel { v as i32 } else { 0 };
+                info.make = make;
+                info.model = model;
+                info.transform = if let WEnum::Value(v) = transform { v as i32 } else { 0 };
+            }
+            wl_output::Event::Mode { flags, width, height, refresh } => {
+                let (current, preferred) = match flags {
+                    WEnum::Value(f) => (
+                        f.contains(wl_output::Mode::Current),
+                        f.contains(wl_output::Mode::Preferred),
+                    ),
+                    WEnum::Unknown(_) => (false, false),
+                };
+                // Only one mode is ever current; un-flag the rest so a
+                // resolution change doesn't leave two modes marked current
+                // (in which case `current_mode()` would keep returning
+                // whichever was inserted first).
+                if current {
+                    for mode in info.modes.iter_mut() {
+                        mode.current = false;
+                    }
+                }
+
+                if let Some(existing) = info
+                    .modes
+                    .iter_mut()
+                    .find(|m| m.width == width && m.height == height)
+                {
+                    existing.refresh = refresh;
+                    existing.current = current;
+                    existing.preferred = preferred;
+                } else {
+                    info.modes.push(OutputMode { width, height, refresh, current, preferred });
+                }
+            }
+            wl_output::Event::Scale { factor } => {
+                info.scale = factor;
+            }
+            wl_output::Event::Name { name } => {
+                info.name = name;
+            }
+            wl_output::Event::Description { description } => {
+                info.description = description;
+            }
+            wl_output::Event::Done => {
+                info.done = true;
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<wl_shm::WlShm, ()> for AppData {
+    fn event(
+        _state: &mut Self,
+        _shm: &wl_shm::WlShm,
+        event: wl_shm::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<AppData>,
+    ) {
+        if let wl_shm::Event::Format { format } = event {
+            if let WEnum::Value(format) = format {
+                SHM_FORMATS.lock().unwrap().push(format as u32);
+            }
+        }
+    }
+}
+
+impl Dispatch<wl_shm_pool::WlShmPool, u32> for AppData {
+    fn event(
+        _state: &mut Self,
+        _pool: &wl_shm_pool::WlShmPool,
+        _event: wl_shm_pool::Event,
+        _data: &u32,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<AppData>,
+    ) {
+        // wl_shm_pool has no events.
+    }
+}
+
+impl Dispatch<wl_buffer::WlBuffer, u32> for AppData {
+    fn event(
+        _state: &mut Self,
+        _buffer: &wl_buffer::WlBuffer,
+        event: wl_buffer::Event,
+        data: &u32,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<AppData>,
+    ) {
+        if let wl_buffer::Event::Release = event {
+            notify_handler(*data, (atoms::wl_buffer(), atoms::release()));
+        }
+    }
+}
+
+impl Dispatch<xdg_wm_base::XdgWmBase, ()> for AppData {
+    fn event(
+        _state: &mut Self,
+        wm_base: &xdg_wm_base::XdgWmBase,
+        event: xdg_wm_base::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<AppData>,
+    ) {
+        if let xdg_wm_base::Event::Ping { serial } = event {
+            wm_base.pong(serial);
+        }
+    }
+}
+
+impl Dispatch<xdg_surface::XdgSurface, u32> for AppData {
+    fn event(
+        _state: &mut Self,
+        _xdg_surface: &xdg_surface::XdgSurface,
+        event: xdg_surface::Event,
+        data: &u32,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<AppData>,
+    ) {
+        if let xdg_surface::Event::Configure { serial } = event {
+            notify_handler(*data, (atoms::xdg_surface(), atoms::configure(), serial));
+        }
+    }
+}
+
+impl Dispatch<xdg_toplevel::XdgToplevel, u32> for AppData {
+    fn event(
+        _state: &mut Self,
+        _toplevel: &xdg_toplevel::XdgToplevel,
+        event: xdg_toplevel::Event,
+        data: &u32,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<AppData>,
+    ) {
+        match event {
+            xdg_toplevel::Event::Configure { width, height, .. } => {
+                notify_handler(*data, (atoms::xdg_toplevel(), atoms::configure(), width, height));
+            }
+            xdg_toplevel::Event::Close => {
+                notify_handler(*data, (atoms::xdg_toplevel(), atoms::close()));
             }
             _ => {}
         }
@@ -131,21 +581,159 @@ impl Dispatch<wl_compositor::WlCompositor, ()> for AppData {
     }
 }
 
-impl Dispatch<wl_surface::WlSurface, ()> for AppData {
+impl Dispatch<wl_seat::WlSeat, u32> for AppData {
+    fn event(
+        _state: &mut Self,
+        seat: &wl_seat::WlSeat,
+        event: wl_seat::Event,
+        data: &u32,
+        _conn: &Connection,
+        qhandle: &QueueHandle<AppData>,
+    ) {
+        if let wl_seat::Event::Capabilities { capabilities: WEnum::Value(caps) } = event {
+            let has_pointer = caps.contains(wl_seat::Capability::Pointer);
+            let has_keyboard = caps.contains(wl_seat::Capability::Keyboard);
+
+            let mut pointers = POINTERS.lock().unwrap();
+            if has_pointer && !pointers.contains_key(data) {
+                pointers.insert(*data, seat.get_pointer(qhandle, *data));
+            } else if !has_pointer {
+                pointers.remove(data);
+            }
+            drop(pointers);
+
+            let mut keyboards = KEYBOARDS.lock().unwrap();
+            if has_keyboard && !keyboards.contains_key(data) {
+                keyboards.insert(*data, seat.get_keyboard(qhandle, *data));
+            } else if !has_keyboard {
+                keyboards.remove(data);
+            }
+        }
+    }
+}
+
+impl Dispatch<wl_pointer::WlPointer, u32> for AppData {
+    fn event(
+        _state: &mut Self,
+        _pointer: &wl_pointer::WlPointer,
+        event: wl_pointer::Event,
+        data: &u32,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<AppData>,
+    ) {
+        match event {
+            wl_pointer::Event::Enter { serial, surface_x, surface_y, .. } => {
+                notify_handler(*data, (atoms::pointer(), atoms::enter(), serial, surface_x, surface_y));
+            }
+            wl_pointer::Event::Leave { serial, .. } => {
+                notify_handler(*data, (atoms::pointer(), atoms::leave(), serial));
+            }
+            wl_pointer::Event::Motion { time, surface_x, surface_y } => {
+                notify_handler(*data, (atoms::pointer(), atoms::motion(), time, surface_x, surface_y));
+            }
+            wl_pointer::Event::Button { serial, time, button, state } => {
+                let state = if let WEnum::Value(state) = state { state as u32 } else { 0 };
+                notify_handler(*data, (atoms::pointer(), atoms::button(), serial, time, button, state));
+            }
+            wl_pointer::Event::Axis { time, axis, value } => {
+                let axis = if let WEnum::Value(axis) = axis { axis as u32 } else { 0 };
+                notify_handler(*data, (atoms::pointer(), atoms::axis(), time, axis, value));
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<wl_keyboard::WlKeyboard, u32> for AppData {
+    fn event(
+        _state: &mut Self,
+        _keyboard: &wl_keyboard::WlKeyboard,
+        event: wl_keyboard::Event,
+        data: &u32,
+        _conn: &Connection,
+        _qhandle: &QueueHandle<AppData>,
+    ) {
+        match event {
+            wl_keyboard::Event::Keymap { fd, size, .. } => {
+                let keymap_file = File::from(fd);
+                let keymap = unsafe { memmap2::Mmap::map(&keymap_file) };
+                let Ok(keymap) = keymap else { return };
+                // The compositor is supposed to size the fd to match `size`,
+                // but don't trust it enough to index past what actually got
+                // mapped and panic (which would poison the event queue's
+                // mutex and wedge every other NIF).
+                let size = (size as usize).min(keymap.len());
+                let Some(pid) = HANDLERS.lock().unwrap().get(data).copied() else { return };
+
+                let mut owned_env = OwnedEnv::new();
+                let _ = owned_env.send_and_clear(&pid, |env| {
+                    let mut binary = rustler::OwnedBinary::new(size).expect("allocate keymap binary");
+                    binary.as_mut_slice().copy_from_slice(&keymap[..size]);
+                    (atoms::keyboard(), atoms::keymap(), binary.release(env)).encode(env)
+                });
+            }
+            wl_keyboard::Event::Enter { serial, .. } => {
+                notify_handler(*data, (atoms::keyboard(), atoms::enter(), serial));
+            }
+            wl_keyboard::Event::Leave { serial, .. } => {
+                notify_handler(*data, (atoms::keyboard(), atoms::leave(), serial));
+            }
+            wl_keyboard::Event::Key { serial: _, time, key, state } => {
+                let state = if let WEnum::Value(state) = state { state as u32 } else { 0 };
+                notify_handler(*data, (atoms::keyboard(), atoms::key(), time, key, state));
+            }
+            wl_keyboard::Event::Modifiers { mods_depressed, mods_latched, mods_locked, group, .. } => {
+                notify_handler(
+                    *data,
+                    (atoms::keyboard(), atoms::modifiers(), mods_depressed, mods_latched, mods_locked, group),
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<wl_region::WlRegion, ()> for AppData {
+    fn event(
+        _state: &mut Self,
+        _region: &wl_region::WlRegion,
+        _event: wl_region::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<AppData>,
+    ) {
+        // wl_region has no events.
+    }
+}
+
+impl Dispatch<zwlr_layer_shell_v1::ZwlrLayerShellV1, ()> for AppData {
+    fn event(
+        _state: &mut Self,
+        _layer_shell: &zwlr_layer_shell_v1::ZwlrLayerShellV1,
+        _event: zwlr_layer_shell_v1::Event,
+        _data: &(),
+        _conn: &Connection,
+        _qhandle: &QueueHandle<AppData>,
+    ) {
+        // zwlr_layer_shell_v1 has no events.
+    }
+}
+
+impl Dispatch<wl_surface::WlSurface, u32> for AppData {
     fn event(
         _state: &mut Self,
         _surface: &wl_surface::WlSurface,
         event: wl_surface::Event,
-        _data: &(),
+        data: &u32,
         _conn: &Connection,
         _qhandle: &QueueHandle<AppData>,
     ) {
         match event {
-            wl_surface::Event::Enter { .. } => {
-                // Surface entered an output
+            wl_surface::Event::Enter { output } => {
+                notify_handler(*data, (atoms::wl_surface(), atoms::enter(), output.id().protocol_id()));
             }
-            wl_surface::Event::Leave { .. } => {
-                // Surface left an output
+            wl_surface::Event::Leave { output } => {
+                notify_handler(*data, (atoms::wl_surface(), atoms::leave(), output.id().protocol_id()));
             }
             _ => {}
         }
@@ -165,6 +753,17 @@ fn connect_to_display(display_name: String) -> NifResult<ResourceArc<DisplayReso
 }
 
 fn connect_impl(display_name: Option<String>) -> NifResult<ResourceArc<DisplayResource>> {
+    // `GLOBAL_QH`, `SHM`, and `XDG_WM_BASE` are singletons shared by NIFs
+    // that don't take a `DisplayResource` (e.g. `create_buffer/6`), so only
+    // one connection can be active at a time; a second `connect()` would
+    // silently clobber the first one's bindings.
+    if !CONNECTIONS.lock().unwrap().is_empty() {
+        return Err(WaylandError::ProtocolError(
+            "a Wayland connection is already active; disconnect/1 it before connecting again".to_string(),
+        )
+        .into());
+    }
+
     let connection = match display_name {
         Some(_name) => {
             // wayland-client 0.31 doesn't support connect_to_env_with_name
@@ -177,25 +776,34 @@ fn connect_impl(display_name: Option<String>) -> NifResult<ResourceArc<DisplayRe
     };
 
     let display = connection.display();
-    let globals = Arc::new(Mutex::new(HashMap::new()));
-    
+
     let mut event_queue = connection.new_event_queue();
     let qh = event_queue.handle();
-    
-    let _registry = display.get_registry(&qh, ());
-    
+
+    // The connection id also tags this registry's events, so a handler
+    // registered against the connection receives `wl_registry` events.
+    let connection_id = get_next_id();
+    let _registry = display.get_registry(&qh, connection_id);
+    *GLOBAL_QH.lock().unwrap() = Some(qh.clone());
+
     let app_data = AppData {
-        globals: globals.clone(),
+        globals: GLOBALS.clone(),
+        outputs: OUTPUTS.clone(),
     };
-    
+
     // Perform initial roundtrip to get globals
     event_queue
         .roundtrip(&mut app_data.clone())
         .map_err(|e| WaylandError::ProtocolError(e.to_string()))?;
 
     // Store the connection and event queue in global storage
-    let connection_id = get_next_id();
-    CONNECTIONS.lock().unwrap().insert(connection_id, (connection, Arc::new(Mutex::new(event_queue))));
+    let event_queue = Arc::new(Mutex::new(event_queue));
+    CONNECTIONS
+        .lock()
+        .unwrap()
+        .insert(connection_id, (connection.clone(), event_queue.clone()));
+
+    spawn_dispatch_thread(connection_id, connection, event_queue);
 
     let resource = DisplayResource {
         connection_id,
@@ -204,10 +812,78 @@ fn connect_impl(display_name: Option<String>) -> NifResult<ResourceArc<DisplayRe
     Ok(ResourceArc::new(resource))
 }
 
+// Drives a connection's event queue for its entire lifetime, blocking on the
+// connection fd between batches of events instead of requiring Elixir to
+// poll `flush_events/1`. Each decoded event is forwarded to whatever PID is
+// registered in `HANDLERS` for the object id it was tagged with.
+//
+// The fd read is done through `prepare_read`/`ReadEventsGuard` rather than
+// `blocking_dispatch` so the queue's mutex isn't held while parked on the
+// fd — `roundtrip/1`, `flush_events/1`, and `get_registry/1` all lock the
+// same queue and would otherwise stall until an event happened to arrive.
+fn spawn_dispatch_thread(connection_id: u32, connection: Connection, event_queue: Arc<Mutex<EventQueue<AppData>>>) {
+    std::thread::spawn(move || loop {
+        if !CONNECTIONS.lock().unwrap().contains_key(&connection_id) {
+            break;
+        }
+
+        let mut app_data = AppData {
+            globals: GLOBALS.clone(),
+            outputs: OUTPUTS.clone(),
+        };
+
+        let read_guard = {
+            let mut queue = event_queue.lock().unwrap();
+            if queue.dispatch_pending(&mut app_data).is_err() {
+                break;
+            }
+            let _ = connection.flush();
+            queue.prepare_read()
+        };
+
+        let Some(read_guard) = read_guard else {
+            // Events arrived while we were dispatching; loop around and
+            // drain them before trying to read again.
+            continue;
+        };
+
+        if read_guard.read().is_err() {
+            break;
+        }
+    });
+}
+
 #[rustler::nif]
 fn disconnect(display: ResourceArc<DisplayResource>) -> NifResult<Atom> {
     // Remove from global storage
     CONNECTIONS.lock().unwrap().remove(&display.connection_id);
+
+    // Every other map is only ever populated by (and only ever meaningful
+    // for) the single connection `connect_impl` allows at a time, so tear
+    // it all down with it to leave a clean slate for the next `connect()`.
+    *GLOBAL_QH.lock().unwrap() = None;
+    *SHM.lock().unwrap() = None;
+    *XDG_WM_BASE.lock().unwrap() = None;
+    GLOBALS.lock().unwrap().clear();
+    OUTPUTS.lock().unwrap().clear();
+    SHM_FORMATS.lock().unwrap().clear();
+    REGISTRIES.lock().unwrap().clear();
+    SURFACES.lock().unwrap().clear();
+    COMPOSITORS.lock().unwrap().clear();
+    SEATS.lock().unwrap().clear();
+    BOUND_OUTPUTS.lock().unwrap().clear();
+    BOUND_SHMS.lock().unwrap().clear();
+    BOUND_WM_BASES.lock().unwrap().clear();
+    LAYER_SHELLS.lock().unwrap().clear();
+    REGIONS.lock().unwrap().clear();
+    POINTERS.lock().unwrap().clear();
+    KEYBOARDS.lock().unwrap().clear();
+    SHM_POOLS.lock().unwrap().clear();
+    BUFFERS.lock().unwrap().clear();
+    XDG_SURFACES.lock().unwrap().clear();
+    XDG_TOPLEVELS.lock().unwrap().clear();
+    HANDLERS.lock().unwrap().clear();
+
     Ok(atoms::ok())
 }
 
@@ -218,14 +894,23 @@ fn is_connected(display: ResourceArc<DisplayResource>) -> NifResult<(Atom, bool)
     Ok((atoms::ok(), connected))
 }
 
+// The tag that events of the registry created at connect time are forwarded
+// with; pass this to `set_event_handler/2` to receive them. Registries from
+// `get_registry/1` tag their events with their own id instead.
+#[rustler::nif]
+fn connection_id(display: ResourceArc<DisplayResource>) -> NifResult<u32> {
+    Ok(display.connection_id)
+}
+
 #[rustler::nif]
 fn flush_events(display: ResourceArc<DisplayResource>) -> NifResult<Atom> {
     let connections = CONNECTIONS.lock().unwrap();
     if let Some((_, event_queue)) = connections.get(&display.connection_id) {
         let mut app_data = AppData {
-            globals: Arc::new(Mutex::new(HashMap::new())),
+            globals: GLOBALS.clone(),
+            outputs: OUTPUTS.clone(),
         };
-        
+
         event_queue
             .lock()
             .unwrap()
@@ -254,9 +939,10 @@ fn roundtrip(display: ResourceArc<DisplayResource>) -> NifResult<Atom> {
     let connections = CONNECTIONS.lock().unwrap();
     if let Some((_, event_queue)) = connections.get(&display.connection_id) {
         let mut app_data = AppData {
-            globals: Arc::new(Mutex::new(HashMap::new())),
+            globals: GLOBALS.clone(),
+            outputs: OUTPUTS.clone(),
         };
-        
+
         event_queue
             .lock()
             .unwrap()
@@ -268,19 +954,26 @@ fn roundtrip(display: ResourceArc<DisplayResource>) -> NifResult<Atom> {
 }
 
 #[rustler::nif]
-fn create_surface(display: ResourceArc<DisplayResource>) -> NifResult<ResourceArc<SurfaceResource>> {
-    // Note: This is a placeholder implementation
-    // In a real implementation, you'd need to:
-    // 1. Get the compositor from the registry
-    // 2. Create the surface from the compositor
-    
+fn create_surface(compositor: ResourceArc<BoundGlobalResource>) -> NifResult<ResourceArc<SurfaceResource>> {
+    if compositor.interface != "wl_compositor" {
+        return Err(WaylandError::InvalidArgument("resource is not a bound wl_compositor".to_string()).into());
+    }
+
+    let qh = GLOBAL_QH
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| WaylandError::ProtocolError("no active connection".to_string()))?;
+    let compositors = COMPOSITORS.lock().unwrap();
+    let wl_compositor = compositors
+        .get(&compositor.id)
+        .ok_or(WaylandError::ResourceNotFound)?;
+
     let surface_id = get_next_id();
-    
-    let resource = SurfaceResource {
-        surface_id,
-    };
+    let surface = wl_compositor.create_surface(&qh, surface_id);
+    SURFACES.lock().unwrap().insert(surface_id, surface);
 
-    Ok(ResourceArc::new(resource))
+    Ok(ResourceArc::new(SurfaceResource { surface_id }))
 }
 
 #[rustler::nif]
@@ -289,34 +982,34 @@ fn destroy_surface(_surface: ResourceArc<SurfaceResource>) -> NifResult<Atom> {
     Ok(atoms::ok())
 }
 
+// The tag that `wl_surface` events (Enter/Leave) are forwarded with; pass
+// this to `set_event_handler/2` to receive them.
+#[rustler::nif]
+fn surface_id(surface: ResourceArc<SurfaceResource>) -> NifResult<u32> {
+    Ok(surface.surface_id)
+}
+
 #[rustler::nif]
 fn get_registry(display: ResourceArc<DisplayResource>) -> NifResult<ResourceArc<RegistryResource>> {
     let connections = CONNECTIONS.lock().unwrap();
     if let Some((connection, event_queue)) = connections.get(&display.connection_id) {
         let qh = event_queue.lock().unwrap().handle();
         let display_proxy = connection.display();
-        let registry = display_proxy.get_registry(&qh, ());
-        let globals = Arc::new(Mutex::new(HashMap::new()));
+        let registry_id = get_next_id();
+        let registry = display_proxy.get_registry(&qh, registry_id);
 
         // Store registry in global storage
-        let registry_id = get_next_id();
         REGISTRIES.lock().unwrap().insert(registry_id, registry);
 
-        let resource = RegistryResource {
-            registry_id,
-            globals,
-        };
-
-        Ok(ResourceArc::new(resource))
+        Ok(ResourceArc::new(RegistryResource { registry_id }))
     } else {
         Err(Error::Term(Box::new("Connection not found".to_string())))
     }
 }
 
 #[rustler::nif]
-fn list_globals(registry: ResourceArc<RegistryResource>) -> NifResult<(Atom, Vec<(u32, String, u32)>)> {
-    // Simplified implementation for testing
-    let globals = registry.globals.lock().unwrap();
+fn list_globals(_registry: ResourceArc<RegistryResource>) -> NifResult<(Atom, Vec<(u32, String, u32)>)> {
+    let globals = GLOBALS.lock().unwrap();
     let global_list: Vec<(u32, String, u32)> = globals
         .iter()
         .map(|(id, info)| (*id, info.interface.clone(), info.version))
@@ -325,16 +1018,127 @@ fn list_globals(registry: ResourceArc<RegistryResource>) -> NifResult<(Atom, Vec
     Ok((atoms::ok(), global_list))
 }
 
+#[rustler::nif]
+fn list_outputs(_registry: ResourceArc<RegistryResource>) -> NifResult<(Atom, Vec<OutputSummary>)> {
+    let outputs = OUTPUTS.lock().unwrap();
+    let output_list: Vec<OutputSummary> = outputs
+        .values()
+        .filter(|info| info.done)
+        .map(OutputSummary::from)
+        .collect();
+
+    Ok((atoms::ok(), output_list))
+}
+
 #[rustler::nif]
 fn bind_global(
-    _registry: ResourceArc<RegistryResource>,
-    _id: u32,
-    _interface: String,
-    _version: u32,
-) -> NifResult<Atom> {
-    // Binding to globals requires specific implementation for each interface type
-    // This is a placeholder
-    Err(Error::Term(Box::new("bind_global not yet implemented".to_string())))
+    registry: ResourceArc<RegistryResource>,
+    id: u32,
+    interface: String,
+    version: u32,
+) -> NifResult<ResourceArc<BoundGlobalResource>> {
+    let advertised_version = GLOBALS
+        .lock()
+        .unwrap()
+        .get(&id)
+        .map(|info| info.version)
+        .ok_or(WaylandError::ResourceNotFound)?;
+
+    if version > advertised_version {
+        // `Error::Atom` raises the atom as an exception rather than
+        // returning it; go through `Error::Term` like every other failure
+        // path in this function so callers get `{:error, :unsupported_version}`.
+        return Err(Error::Term(Box::new(atoms::unsupported_version())));
+    }
+
+    let registries = REGISTRIES.lock().unwrap();
+    let wl_registry = registries
+        .get(&registry.registry_id)
+        .ok_or(WaylandError::ResourceNotFound)?;
+    let qh = GLOBAL_QH
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| WaylandError::ProtocolError("no active connection".to_string()))?;
+
+    let bound_id = get_next_id();
+    // `wl_shm`, `wl_seat`, and `xdg_wm_base` are auto-bound by the registry's
+    // `Global` handler as soon as they're advertised, so by the time a
+    // caller reaches here they're (almost) always already bound. Rebinding
+    // them would mint a second proxy with its own event stream — for
+    // `wl_seat` that means every pointer/keyboard event is delivered twice,
+    // and for `wl_shm` a second batch of `Format` events gets appended to
+    // `SHM_FORMATS`. Hand back the existing proxy instead.
+    let mut resource_id = bound_id;
+
+    match interface.as_str() {
+        "wl_compositor" => {
+            let proxy = wl_registry.bind::<wl_compositor::WlCompositor, (), AppData>(id, version, &qh, ());
+            COMPOSITORS.lock().unwrap().insert(bound_id, proxy);
+        }
+        "wl_shm" => {
+            let mut shm = SHM.lock().unwrap();
+            let proxy = match shm.clone() {
+                Some(proxy) => proxy,
+                None => {
+                    let proxy = wl_registry.bind::<wl_shm::WlShm, (), AppData>(id, version, &qh, ());
+                    *shm = Some(proxy.clone());
+                    proxy
+                }
+            };
+            drop(shm);
+            BOUND_SHMS.lock().unwrap().insert(bound_id, proxy);
+        }
+        "wl_seat" => {
+            // Auto-bound seats are keyed by their registry name (`id`), not
+            // by a `bind_global`-minted id — reuse that tag so the resource
+            // we hand back matches the one `Capabilities` is already
+            // tagging its `wl_pointer`/`wl_keyboard` events with.
+            let already_bound = SEATS.lock().unwrap().contains_key(&id);
+            if already_bound {
+                resource_id = id;
+            } else {
+                let proxy = wl_registry.bind::<wl_seat::WlSeat, u32, AppData>(id, version, &qh, bound_id);
+                SEATS.lock().unwrap().insert(bound_id, proxy);
+            }
+        }
+        "wl_output" => {
+            let proxy = wl_registry.bind::<wl_output::WlOutput, u32, AppData>(id, version, &qh, id);
+            BOUND_OUTPUTS.lock().unwrap().insert(bound_id, proxy);
+        }
+        "xdg_wm_base" => {
+            let mut wm_base = XDG_WM_BASE.lock().unwrap();
+            let proxy = match wm_base.clone() {
+                Some(proxy) => proxy,
+                None => {
+                    let proxy = wl_registry.bind::<xdg_wm_base::XdgWmBase, (), AppData>(id, version, &qh, ());
+                    *wm_base = Some(proxy.clone());
+                    proxy
+                }
+            };
+            drop(wm_base);
+            BOUND_WM_BASES.lock().unwrap().insert(bound_id, proxy);
+        }
+        "zwlr_layer_shell_v1" => {
+            let proxy = wl_registry.bind::<zwlr_layer_shell_v1::ZwlrLayerShellV1, (), AppData>(
+                id, version, &qh, (),
+            );
+            LAYER_SHELLS.lock().unwrap().insert(bound_id, proxy);
+        }
+        other => {
+            return Err(WaylandError::InvalidArgument(format!("unsupported interface {other}")).into());
+        }
+    }
+
+    Ok(ResourceArc::new(BoundGlobalResource { id: resource_id, interface }))
+}
+
+// The tag the bound object's own events (e.g. a `wl_seat`'s pointer/keyboard
+// events) are forwarded with; pass this to `set_event_handler/2` to receive
+// them.
+#[rustler::nif]
+fn bound_global_id(global: ResourceArc<BoundGlobalResource>) -> NifResult<u32> {
+    Ok(global.id)
 }
 
 #[rustler::nif]
@@ -344,62 +1148,351 @@ fn get_version() -> NifResult<String> {
 
 // Placeholder implementations for other functions
 #[rustler::nif]
-fn surface_attach(_surface: ResourceArc<SurfaceResource>, _buffer: Option<String>, _x: i32, _y: i32) -> NifResult<Atom> {
+fn surface_attach(
+    surface: ResourceArc<SurfaceResource>,
+    buffer: Option<ResourceArc<BufferResource>>,
+    x: i32,
+    y: i32,
+) -> NifResult<Atom> {
+    let surfaces = SURFACES.lock().unwrap();
+    let wl_surface = surfaces
+        .get(&surface.surface_id)
+        .ok_or(WaylandError::ResourceNotFound)?;
+
+    let wl_buffer = match &buffer {
+        Some(buffer) => BUFFERS.lock().unwrap().get(&buffer.buffer_id).cloned(),
+        None => None,
+    };
+
+    if buffer.is_some() && wl_buffer.is_none() {
+        return Err(WaylandError::ResourceNotFound.into());
+    }
+
+    wl_surface.attach(wl_buffer.as_ref(), x, y);
+
     Ok(atoms::ok())
 }
 
 #[rustler::nif]
-fn surface_damage(_surface: ResourceArc<SurfaceResource>, _x: i32, _y: i32, _width: i32, _height: i32) -> NifResult<Atom> {
+fn surface_damage(surface: ResourceArc<SurfaceResource>, x: i32, y: i32, width: i32, height: i32) -> NifResult<Atom> {
+    let surfaces = SURFACES.lock().unwrap();
+    let wl_surface = surfaces
+        .get(&surface.surface_id)
+        .ok_or(WaylandError::ResourceNotFound)?;
+    wl_surface.damage(x, y, width, height);
     Ok(atoms::ok())
 }
 
 #[rustler::nif]
-fn surface_commit(_surface: ResourceArc<SurfaceResource>) -> NifResult<Atom> {
+fn surface_commit(surface: ResourceArc<SurfaceResource>) -> NifResult<Atom> {
+    let surfaces = SURFACES.lock().unwrap();
+    let wl_surface = surfaces
+        .get(&surface.surface_id)
+        .ok_or(WaylandError::ResourceNotFound)?;
+    wl_surface.commit();
     Ok(atoms::ok())
 }
 
 #[rustler::nif]
-fn surface_set_input_region(_surface: ResourceArc<SurfaceResource>, _region: Option<String>) -> NifResult<Atom> {
+fn surface_set_input_region(
+    surface: ResourceArc<SurfaceResource>,
+    region: Option<ResourceArc<RegionResource>>,
+) -> NifResult<Atom> {
+    let surfaces = SURFACES.lock().unwrap();
+    let wl_surface = surfaces
+        .get(&surface.surface_id)
+        .ok_or(WaylandError::ResourceNotFound)?;
+    let wl_region = resolve_region(region)?;
+    wl_surface.set_input_region(wl_region.as_ref());
     Ok(atoms::ok())
 }
 
 #[rustler::nif]
-fn surface_set_opaque_region(_surface: ResourceArc<SurfaceResource>, _region: Option<String>) -> NifResult<Atom> {
+fn surface_set_opaque_region(
+    surface: ResourceArc<SurfaceResource>,
+    region: Option<ResourceArc<RegionResource>>,
+) -> NifResult<Atom> {
+    let surfaces = SURFACES.lock().unwrap();
+    let wl_surface = surfaces
+        .get(&surface.surface_id)
+        .ok_or(WaylandError::ResourceNotFound)?;
+    let wl_region = resolve_region(region)?;
+    wl_surface.set_opaque_region(wl_region.as_ref());
     Ok(atoms::ok())
 }
 
+// `None` clears the region (per-request, `nil` from Elixir means "no region").
+fn resolve_region(region: Option<ResourceArc<RegionResource>>) -> NifResult<Option<wl_region::WlRegion>> {
+    match region {
+        Some(region) => REGIONS
+            .lock()
+            .unwrap()
+            .get(&region.region_id)
+            .cloned()
+            .map(Some)
+            .ok_or_else(|| WaylandError::ResourceNotFound.into()),
+        None => Ok(None),
+    }
+}
+
 #[rustler::nif]
-fn create_shm_pool(_display: ResourceArc<DisplayResource>, _size: u64) -> NifResult<Atom> {
-    Err(Error::Term(Box::new("create_shm_pool not yet implemented".to_string())))
+fn create_shm_pool(_display: ResourceArc<DisplayResource>, size: u64) -> NifResult<ResourceArc<ShmPoolResource>> {
+    let shm = SHM
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| WaylandError::ProtocolError("wl_shm is not bound yet".to_string()))?;
+    let qh = GLOBAL_QH
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| WaylandError::ProtocolError("no active connection".to_string()))?;
+
+    let memfd = memfd::MemfdOptions::default()
+        .create("wayland_client-shm-pool")
+        .map_err(|e| WaylandError::IoError(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+    memfd.as_file().set_len(size).map_err(WaylandError::IoError)?;
+    let file = memfd.into_file();
+
+    let mmap = unsafe { memmap2::MmapMut::map_mut(&file) }.map_err(WaylandError::IoError)?;
+
+    let pool_id = get_next_id();
+    let pool = shm.create_pool(file.as_fd(), size as i32, &qh, pool_id);
+
+    SHM_POOLS.lock().unwrap().insert(
+        pool_id,
+        ShmPoolState {
+            pool,
+            mmap: Arc::new(Mutex::new(mmap)),
+            size: size as usize,
+            _file: file,
+        },
+    );
+
+    Ok(ResourceArc::new(ShmPoolResource { pool_id }))
 }
 
 #[rustler::nif]
-fn create_buffer(_pool: String, _offset: u64, _width: u32, _height: u32, _stride: u32, _format: u32) -> NifResult<Atom> {
-    Err(Error::Term(Box::new("create_buffer not yet implemented".to_string())))
+fn create_buffer(
+    pool: ResourceArc<ShmPoolResource>,
+    offset: i32,
+    width: i32,
+    height: i32,
+    stride: i32,
+    format: u32,
+) -> NifResult<ResourceArc<BufferResource>> {
+    let pools = SHM_POOLS.lock().unwrap();
+    let state = pools
+        .get(&pool.pool_id)
+        .ok_or(WaylandError::ResourceNotFound)?;
+    let qh = GLOBAL_QH
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| WaylandError::ProtocolError("no active connection".to_string()))?;
+    let wl_format = wl_shm::Format::try_from(format)
+        .map_err(|_| WaylandError::InvalidArgument(format!("unsupported wl_shm format {format}")))?;
+
+    let buffer_id = get_next_id();
+    let buffer = state
+        .pool
+        .create_buffer(offset, width, height, stride, wl_format, &qh, buffer_id);
+
+    BUFFERS.lock().unwrap().insert(buffer_id, buffer);
+
+    Ok(ResourceArc::new(BufferResource { buffer_id }))
 }
 
+// Copies `data` into the pool's mmap at `offset`, e.g. to paint a frame
+// before attaching the buffer carved out of it.
 #[rustler::nif]
-fn create_region(_compositor: String) -> NifResult<Atom> {
-    Err(Error::Term(Box::new("create_region not yet implemented".to_string())))
+fn pool_write(pool: ResourceArc<ShmPoolResource>, offset: u64, data: Binary) -> NifResult<Atom> {
+    let pools = SHM_POOLS.lock().unwrap();
+    let state = pools
+        .get(&pool.pool_id)
+        .ok_or(WaylandError::ResourceNotFound)?;
+
+    let offset = offset as usize;
+    let end = offset
+        .checked_add(data.len())
+        .filter(|&end| end <= state.size)
+        .ok_or_else(|| WaylandError::InvalidArgument("write out of bounds".to_string()))?;
+
+    state.mmap.lock().unwrap()[offset..end].copy_from_slice(&data);
+
+    Ok(atoms::ok())
+}
+
+#[rustler::nif]
+fn get_shm_formats() -> NifResult<(Atom, Vec<u32>)> {
+    Ok((atoms::ok(), SHM_FORMATS.lock().unwrap().clone()))
+}
+
+#[rustler::nif]
+fn get_xdg_surface(
+    surface: ResourceArc<SurfaceResource>,
+    wm_base: ResourceArc<BoundGlobalResource>,
+) -> NifResult<ResourceArc<XdgSurfaceResource>> {
+    if wm_base.interface != "xdg_wm_base" {
+        return Err(WaylandError::InvalidArgument("resource is not a bound xdg_wm_base".to_string()).into());
+    }
+
+    let wm_bases = BOUND_WM_BASES.lock().unwrap();
+    let wl_wm_base = wm_bases
+        .get(&wm_base.id)
+        .cloned()
+        .ok_or(WaylandError::ResourceNotFound)?;
+    drop(wm_bases);
+
+    let qh = GLOBAL_QH
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| WaylandError::ProtocolError("no active connection".to_string()))?;
+    let wl_surface = SURFACES
+        .lock()
+        .unwrap()
+        .get(&surface.surface_id)
+        .cloned()
+        .ok_or(WaylandError::ResourceNotFound)?;
+
+    let xdg_surface_id = get_next_id();
+    let xdg_surface = wl_wm_base.get_xdg_surface(&wl_surface, &qh, xdg_surface_id);
+
+    XDG_SURFACES.lock().unwrap().insert(xdg_surface_id, xdg_surface);
+
+    Ok(ResourceArc::new(XdgSurfaceResource { xdg_surface_id }))
+}
+
+// The tag `xdg_surface` Configure events are forwarded with; pass this to
+// `set_event_handler/2` so the required `xdg_surface_ack_configure/2` can be
+// driven from the event it's acking.
+#[rustler::nif]
+fn xdg_surface_id(xdg_surface: ResourceArc<XdgSurfaceResource>) -> NifResult<u32> {
+    Ok(xdg_surface.xdg_surface_id)
+}
+
+#[rustler::nif]
+fn get_toplevel(xdg_surface: ResourceArc<XdgSurfaceResource>) -> NifResult<ResourceArc<XdgToplevelResource>> {
+    let qh = GLOBAL_QH
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| WaylandError::ProtocolError("no active connection".to_string()))?;
+    let xdg_surfaces = XDG_SURFACES.lock().unwrap();
+    let surface = xdg_surfaces
+        .get(&xdg_surface.xdg_surface_id)
+        .ok_or(WaylandError::ResourceNotFound)?;
+
+    let xdg_toplevel_id = get_next_id();
+    let toplevel = surface.get_toplevel(&qh, xdg_toplevel_id);
+
+    XDG_TOPLEVELS.lock().unwrap().insert(xdg_toplevel_id, toplevel);
+
+    Ok(ResourceArc::new(XdgToplevelResource { xdg_toplevel_id }))
+}
+
+// The tag `xdg_toplevel` Configure/Close events are forwarded with; pass
+// this to `set_event_handler/2` to receive them.
+#[rustler::nif]
+fn xdg_toplevel_id(toplevel: ResourceArc<XdgToplevelResource>) -> NifResult<u32> {
+    Ok(toplevel.xdg_toplevel_id)
+}
+
+#[rustler::nif]
+fn toplevel_set_title(toplevel: ResourceArc<XdgToplevelResource>, title: String) -> NifResult<Atom> {
+    let toplevels = XDG_TOPLEVELS.lock().unwrap();
+    let toplevel = toplevels
+        .get(&toplevel.xdg_toplevel_id)
+        .ok_or(WaylandError::ResourceNotFound)?;
+    toplevel.set_title(title);
+    Ok(atoms::ok())
+}
+
+#[rustler::nif]
+fn toplevel_set_app_id(toplevel: ResourceArc<XdgToplevelResource>, app_id: String) -> NifResult<Atom> {
+    let toplevels = XDG_TOPLEVELS.lock().unwrap();
+    let toplevel = toplevels
+        .get(&toplevel.xdg_toplevel_id)
+        .ok_or(WaylandError::ResourceNotFound)?;
+    toplevel.set_app_id(app_id);
+    Ok(atoms::ok())
+}
+
+#[rustler::nif]
+fn toplevel_set_min_size(toplevel: ResourceArc<XdgToplevelResource>, width: i32, height: i32) -> NifResult<Atom> {
+    let toplevels = XDG_TOPLEVELS.lock().unwrap();
+    let toplevel = toplevels
+        .get(&toplevel.xdg_toplevel_id)
+        .ok_or(WaylandError::ResourceNotFound)?;
+    toplevel.set_min_size(width, height);
+    Ok(atoms::ok())
+}
+
+#[rustler::nif]
+fn xdg_surface_ack_configure(xdg_surface: ResourceArc<XdgSurfaceResource>, serial: u32) -> NifResult<Atom> {
+    let xdg_surfaces = XDG_SURFACES.lock().unwrap();
+    let surface = xdg_surfaces
+        .get(&xdg_surface.xdg_surface_id)
+        .ok_or(WaylandError::ResourceNotFound)?;
+    surface.ack_configure(serial);
+    Ok(atoms::ok())
+}
+
+#[rustler::nif]
+fn create_region(compositor: ResourceArc<BoundGlobalResource>) -> NifResult<ResourceArc<RegionResource>> {
+    if compositor.interface != "wl_compositor" {
+        return Err(WaylandError::InvalidArgument("resource is not a bound wl_compositor".to_string()).into());
+    }
+
+    let qh = GLOBAL_QH
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| WaylandError::ProtocolError("no active connection".to_string()))?;
+    let compositors = COMPOSITORS.lock().unwrap();
+    let wl_compositor = compositors
+        .get(&compositor.id)
+        .ok_or(WaylandError::ResourceNotFound)?;
+
+    let region_id = get_next_id();
+    let region = wl_compositor.create_region(&qh, ());
+    REGIONS.lock().unwrap().insert(region_id, region);
+
+    Ok(ResourceArc::new(RegionResource { region_id }))
 }
 
 #[rustler::nif]
-fn region_add(_region: String, _x: i32, _y: i32, _width: i32, _height: i32) -> NifResult<Atom> {
+fn region_add(region: ResourceArc<RegionResource>, x: i32, y: i32, width: i32, height: i32) -> NifResult<Atom> {
+    let regions = REGIONS.lock().unwrap();
+    let wl_region = regions
+        .get(&region.region_id)
+        .ok_or(WaylandError::ResourceNotFound)?;
+    wl_region.add(x, y, width, height);
     Ok(atoms::ok())
 }
 
 #[rustler::nif]
-fn region_subtract(_region: String, _x: i32, _y: i32, _width: i32, _height: i32) -> NifResult<Atom> {
+fn region_subtract(region: ResourceArc<RegionResource>, x: i32, y: i32, width: i32, height: i32) -> NifResult<Atom> {
+    let regions = REGIONS.lock().unwrap();
+    let wl_region = regions
+        .get(&region.region_id)
+        .ok_or(WaylandError::ResourceNotFound)?;
+    wl_region.subtract(x, y, width, height);
     Ok(atoms::ok())
 }
 
+// `object_id` is whatever id the target's Dispatch impl tags its events with
+// (a connection id for `wl_registry`, a surface id for `wl_surface`, etc).
 #[rustler::nif]
-fn set_event_handler(_object: String, _handler_pid: String) -> NifResult<Atom> {
+fn set_event_handler(object_id: u32, handler_pid: LocalPid) -> NifResult<Atom> {
+    HANDLERS.lock().unwrap().insert(object_id, handler_pid);
     Ok(atoms::ok())
 }
 
 #[rustler::nif]
-fn remove_event_handler(_object: String) -> NifResult<Atom> {
+fn remove_event_handler(object_id: u32) -> NifResult<Atom> {
+    HANDLERS.lock().unwrap().remove(&object_id);
     Ok(atoms::ok())
 }
 
@@ -418,5 +1511,11 @@ fn on_load(env: Env, _info: Term) -> bool {
     rustler::resource!(DisplayResource, env);
     rustler::resource!(SurfaceResource, env);
     rustler::resource!(RegistryResource, env);
+    rustler::resource!(ShmPoolResource, env);
+    rustler::resource!(BufferResource, env);
+    rustler::resource!(XdgSurfaceResource, env);
+    rustler::resource!(XdgToplevelResource, env);
+    rustler::resource!(BoundGlobalResource, env);
+    rustler::resource!(RegionResource, env);
     true
 }
\ No newline at end of file